@@ -119,4 +119,33 @@ bench_all!([
     { inline_fields_errors_only : BulkErrorsResponse<Inline, Inline, Inline> },
     { interned_fields_all : BulkResponse<Interned, Interned, Interned> },
     { interned_fields_errors_only : BulkErrorsResponse<Interned, Interned, Interned> }
-]);
\ No newline at end of file
+]);
+
+// `StreamingBulkResponse` doesn't go through `parse::<T>()`, so it can't be
+// plugged into `bench_all!`'s macros - it's benchmarked directly here against
+// the same sample body, to check the up-front allocation it avoids actually
+// pays for the background thread and channel it adds.
+pub mod streaming {
+    use std::io::Cursor;
+    use test::{Bencher, black_box};
+    use elastic_responses::*;
+
+    use super::*;
+
+    #[bench]
+    fn default_all(b: &mut Bencher) {
+        // `from_reader` needs a `'static` reader for its background thread.
+        let response: &'static [u8] = Box::leak(get_response().into_boxed_slice());
+
+        b.iter(|| {
+            let read = Cursor::new(response);
+            let bulk: StreamingBulkResponse = StreamingBulkResponse::from_reader(read);
+
+            for item in bulk.items() {
+                black_box(item);
+            }
+
+            black_box(bulk.took());
+        });
+    }
+}
\ No newline at end of file
@@ -0,0 +1,362 @@
+//! The response from a bulk request.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Read;
+use std::marker::PhantomData;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+#[cfg(test)]
+use std::io::Cursor;
+
+use serde::de::{Deserialize, DeserializeOwned, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_json::{self, Value};
+
+use common::{self, Warnings, WithWarnings};
+use error::ParseResponseError;
+use parsing::{HttpResponseHead, IsOk, MaybeOkResponse, ResponseBody, Unbuffered};
+
+/// A single item in a bulk response, keyed by the action that produced it
+/// (`index`, `create`, `update` or `delete`).
+#[derive(Debug, Deserialize)]
+pub struct BulkItem<TIndex = String, TType = String, TId = String> {
+    #[serde(rename = "_index")]
+    pub index: TIndex,
+    #[serde(rename = "_type")]
+    pub ty: TType,
+    #[serde(rename = "_id")]
+    pub id: TId,
+    pub status: u16,
+    #[serde(default)]
+    pub error: Option<Value>,
+}
+
+/// The response from a bulk request.
+#[derive(Debug, Deserialize)]
+pub struct BulkResponse<TIndex = String, TType = String, TId = String> {
+    took: u64,
+    errors: bool,
+    items: Vec<BTreeMap<String, BulkItem<TIndex, TType, TId>>>,
+    #[serde(skip)]
+    warnings: Warnings,
+}
+
+impl<TIndex, TType, TId> BulkResponse<TIndex, TType, TId> {
+    /// The time taken to execute the bulk request, in milliseconds.
+    pub fn took(&self) -> u64 {
+        self.took
+    }
+
+    /// Whether any of the items in the response failed.
+    pub fn errors(&self) -> bool {
+        self.errors
+    }
+
+    /// Iterate over the individual bulk items.
+    pub fn items(&self) -> impl Iterator<Item = &BulkItem<TIndex, TType, TId>> {
+        self.items.iter().filter_map(|item| item.values().next())
+    }
+
+    /// Get any deprecation warnings returned alongside the response.
+    pub fn warnings(&self) -> &Warnings {
+        &self.warnings
+    }
+}
+
+impl<TIndex, TType, TId> IsOk for BulkResponse<TIndex, TType, TId>
+    where TIndex: DeserializeOwned,
+          TType: DeserializeOwned,
+          TId: DeserializeOwned
+{
+    fn is_ok<B: ResponseBody>(head: HttpResponseHead, body: Unbuffered<B>) -> Result<MaybeOkResponse<B>, ParseResponseError> {
+        common::is_ok_from_status(head, body)
+    }
+}
+
+impl<TIndex, TType, TId> WithWarnings for BulkResponse<TIndex, TType, TId> {
+    fn with_warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = warnings;
+        self
+    }
+}
+
+/// A lazily-decoded bulk response that streams items out of the body
+/// instead of buffering the whole `items` array up front.
+///
+/// Unlike [`BulkResponse`][BulkResponse], this doesn't go through the usual
+/// `parse::<T>()` pipeline: deciding whether a body is ok or an `ApiError`
+/// means being able to inspect it, which defeats the point of not buffering
+/// it. Use this once you already know you're dealing with a successful,
+/// large bulk response and just want to avoid the up-front allocation.
+///
+/// [BulkResponse]: struct.BulkResponse.html
+pub struct StreamingBulkResponse<TIndex = String, TType = String, TId = String> {
+    items: Receiver<BulkItem<TIndex, TType, TId>>,
+    meta: Arc<MetaSlot>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct BulkMeta {
+    took: u64,
+    errors: bool,
+}
+
+type BulkMetaResult = Result<BulkMeta, Arc<ParseResponseError>>;
+
+/// The background thread's decode result, plus a `Condvar` so callers can
+/// block for it without touching the `items` channel.
+type MetaSlot = (Mutex<Option<BulkMetaResult>>, Condvar);
+
+impl<TIndex, TType, TId> StreamingBulkResponse<TIndex, TType, TId>
+    where TIndex: DeserializeOwned + Send + 'static,
+          TType: DeserializeOwned + Send + 'static,
+          TId: DeserializeOwned + Send + 'static
+{
+    /// Start streaming bulk items out of a reader.
+    ///
+    /// Decoding happens on a background thread, one item at a time, so a
+    /// caller can start processing items before the rest of the response
+    /// has arrived.
+    pub fn from_reader<R: Read + Send + 'static>(reader: R) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let meta = Arc::new((Mutex::new(None), Condvar::new()));
+        let meta_writer = meta.clone();
+
+        thread::spawn(move || {
+            let mut de = serde_json::Deserializer::from_reader(reader);
+            let visitor = BulkVisitor { items: tx, _marker: PhantomData };
+
+            let result = de.deserialize_map(visitor).map_err(|e| Arc::new(ParseResponseError::from(e)));
+
+            let (lock, done) = &*meta_writer;
+            *lock.lock().unwrap() = Some(result);
+            done.notify_all();
+        });
+
+        StreamingBulkResponse { items: rx, meta }
+    }
+
+    /// Iterate over the bulk items as they're decoded.
+    ///
+    /// Dropping the response early stops the background thread from
+    /// decoding any further items; dropping just the iterator does not.
+    pub fn items(&self) -> impl Iterator<Item = BulkItem<TIndex, TType, TId>> + '_ {
+        self.items.iter()
+    }
+
+    /// The time taken to execute the bulk request, in milliseconds.
+    ///
+    /// `took` can appear before or after `items` in the response body, so
+    /// this blocks until decoding has finished completely, without consuming
+    /// any items from [`items`][StreamingBulkResponse::items]. Reports `0`
+    /// if decoding the body failed; see
+    /// [`decode_error`][StreamingBulkResponse::decode_error].
+    ///
+    /// [StreamingBulkResponse::items]: struct.StreamingBulkResponse.html#method.items
+    ///
+    /// [StreamingBulkResponse::decode_error]: struct.StreamingBulkResponse.html#method.decode_error
+    pub fn took(&self) -> u64 {
+        self.wait().and_then(Result::ok).map(|meta| meta.took).unwrap_or_default()
+    }
+
+    /// Whether any of the items in the response failed.
+    ///
+    /// Like [`took`][StreamingBulkResponse::took], this blocks until decoding
+    /// has finished, and reports `false` if decoding the body failed; use
+    /// [`decode_error`][StreamingBulkResponse::decode_error] to tell that
+    /// case apart from a response without failed items.
+    ///
+    /// [StreamingBulkResponse::took]: struct.StreamingBulkResponse.html#method.took
+    /// [StreamingBulkResponse::decode_error]: struct.StreamingBulkResponse.html#method.decode_error
+    pub fn errors(&self) -> bool {
+        self.wait().and_then(Result::ok).map(|meta| meta.errors).unwrap_or_default()
+    }
+
+    /// The error that stopped decoding, if the body was truncated or malformed.
+    ///
+    /// Blocks until decoding has finished, same as
+    /// [`took`][StreamingBulkResponse::took] and
+    /// [`errors`][StreamingBulkResponse::errors].
+    ///
+    /// [StreamingBulkResponse::took]: struct.StreamingBulkResponse.html#method.took
+    /// [StreamingBulkResponse::errors]: struct.StreamingBulkResponse.html#method.errors
+    pub fn decode_error(&self) -> Option<Arc<ParseResponseError>> {
+        self.wait().and_then(Result::err)
+    }
+
+    fn wait(&self) -> Option<BulkMetaResult> {
+        let (lock, done) = &*self.meta;
+        let mut meta = lock.lock().unwrap();
+
+        while meta.is_none() {
+            meta = done.wait(meta).unwrap();
+        }
+
+        meta.clone()
+    }
+}
+
+struct BulkVisitor<TIndex, TType, TId> {
+    items: Sender<BulkItem<TIndex, TType, TId>>,
+    _marker: PhantomData<(TIndex, TType, TId)>,
+}
+
+impl<'de, TIndex, TType, TId> Visitor<'de> for BulkVisitor<TIndex, TType, TId>
+    where TIndex: Deserialize<'de>,
+          TType: Deserialize<'de>,
+          TId: Deserialize<'de>
+{
+    type Value = BulkMeta;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a bulk response object")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where A: MapAccess<'de>
+    {
+        let mut meta = BulkMeta::default();
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "took" => meta.took = map.next_value()?,
+                "errors" => meta.errors = map.next_value()?,
+                "items" => map.next_value_seed(ItemsSeed { items: &self.items })?,
+                _ => { map.next_value::<IgnoredAny>()?; }
+            }
+        }
+
+        Ok(meta)
+    }
+}
+
+struct ItemsSeed<'a, TIndex: 'a, TType: 'a, TId: 'a> {
+    items: &'a Sender<BulkItem<TIndex, TType, TId>>,
+}
+
+impl<'de, 'a, TIndex, TType, TId> DeserializeSeed<'de> for ItemsSeed<'a, TIndex, TType, TId>
+    where TIndex: Deserialize<'de>,
+          TType: Deserialize<'de>,
+          TId: Deserialize<'de>
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserializer.deserialize_seq(ItemsVisitor { items: self.items })
+    }
+}
+
+struct ItemsVisitor<'a, TIndex: 'a, TType: 'a, TId: 'a> {
+    items: &'a Sender<BulkItem<TIndex, TType, TId>>,
+}
+
+impl<'de, 'a, TIndex, TType, TId> Visitor<'de> for ItemsVisitor<'a, TIndex, TType, TId>
+    where TIndex: Deserialize<'de>,
+          TType: Deserialize<'de>,
+          TId: Deserialize<'de>
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an array of bulk items")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        while let Some(item) = seq.next_element::<BTreeMap<String, BulkItem<TIndex, TType, TId>>>()? {
+            if let Some(item) = item.into_iter().next().map(|(_, item)| item) {
+                // The receiving end may already be gone if the caller
+                // dropped the response early; just stop decoding in that case.
+                if self.items.send(item).is_err() {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streams_items_regardless_of_meta_field_order() {
+        let body = r#"{
+            "items": [
+                { "index": { "_index": "a", "_type": "doc", "_id": "1", "status": 201 } },
+                { "index": { "_index": "a", "_type": "doc", "_id": "2", "status": 201 } }
+            ],
+            "errors": false,
+            "took": 12
+        }"#;
+
+        let response: StreamingBulkResponse = StreamingBulkResponse::from_reader(Cursor::new(body.as_bytes()));
+
+        let items: Vec<_> = response.items().collect();
+
+        assert_eq!(2, items.len());
+        assert_eq!("1", items[0].id);
+        assert_eq!("2", items[1].id);
+
+        assert_eq!(12, response.took());
+        assert!(!response.errors());
+        assert!(response.decode_error().is_none());
+    }
+
+    #[test]
+    fn meta_fields_appearing_before_items_are_still_reported() {
+        let body = r#"{
+            "took": 5,
+            "errors": true,
+            "items": [
+                { "index": { "_index": "a", "_type": "doc", "_id": "1", "status": 500, "error": { "type": "x" } } }
+            ]
+        }"#;
+
+        let response: StreamingBulkResponse = StreamingBulkResponse::from_reader(Cursor::new(body.as_bytes()));
+
+        assert_eq!(1, response.items().count());
+        assert_eq!(5, response.took());
+        assert!(response.errors());
+    }
+
+    #[test]
+    fn calling_took_before_draining_items_still_blocks_for_the_real_value() {
+        let body = r#"{
+            "items": [
+                { "index": { "_index": "a", "_type": "doc", "_id": "1", "status": 201 } }
+            ],
+            "took": 7,
+            "errors": false
+        }"#;
+
+        let response: StreamingBulkResponse = StreamingBulkResponse::from_reader(Cursor::new(body.as_bytes()));
+
+        // Called immediately, before anything has necessarily been decoded yet.
+        assert_eq!(7, response.took());
+        assert!(!response.errors());
+
+        assert_eq!(1, response.items().count());
+    }
+
+    #[test]
+    fn decode_error_is_surfaced_for_a_malformed_body() {
+        let body = r#"{ "items": [ { "index": { "_index": "a" "#;
+
+        let response: StreamingBulkResponse = StreamingBulkResponse::from_reader(Cursor::new(body.as_bytes()));
+
+        // Drain whatever did make it through before the error.
+        let _: Vec<_> = response.items().collect();
+
+        assert!(response.decode_error().is_some());
+        assert_eq!(0, response.took());
+        assert!(!response.errors());
+    }
+}
@@ -0,0 +1,39 @@
+//! The response from a command that just acknowledges success.
+
+use common::{self, Warnings, WithWarnings};
+use error::ParseResponseError;
+use parsing::{HttpResponseHead, IsOk, MaybeOkResponse, ResponseBody, Unbuffered};
+
+/// The response from a command that just acknowledges success, like deleting an index.
+#[derive(Debug, Default, Deserialize)]
+pub struct CommandResponse {
+    #[serde(default)]
+    acknowledged: bool,
+    #[serde(skip)]
+    warnings: Warnings,
+}
+
+impl CommandResponse {
+    /// Whether the command was acknowledged.
+    pub fn acknowledged(&self) -> bool {
+        self.acknowledged
+    }
+
+    /// Get any deprecation warnings returned alongside the response.
+    pub fn warnings(&self) -> &Warnings {
+        &self.warnings
+    }
+}
+
+impl IsOk for CommandResponse {
+    fn is_ok<B: ResponseBody>(head: HttpResponseHead, body: Unbuffered<B>) -> Result<MaybeOkResponse<B>, ParseResponseError> {
+        common::is_ok_from_status(head, body)
+    }
+}
+
+impl WithWarnings for CommandResponse {
+    fn with_warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = warnings;
+        self
+    }
+}
@@ -0,0 +1,92 @@
+//! Types shared between the different response kinds.
+
+use error::ParseResponseError;
+use parsing::{HttpResponseHead, MaybeOkResponse, ResponseBody, Unbuffered};
+
+/// Deprecation and informational warnings returned by Elasticsearch.
+///
+/// Elasticsearch attaches one `Warning` header per deprecated feature a
+/// request touched. This type collects their values so callers can log or
+/// assert on them without digging through the response head themselves.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Warnings(Vec<String>);
+
+impl Warnings {
+    /// Collect the `Warning` header values from a response head.
+    pub fn from_head(head: &HttpResponseHead) -> Self {
+        let warnings = head.headers()
+            .iter()
+            .filter(|&(name, _)| name.eq_ignore_ascii_case("warning"))
+            .map(|(_, value)| value.to_owned())
+            .collect();
+
+        Warnings(warnings)
+    }
+
+    /// Iterate over the raw warning strings.
+    pub fn iter(&self) -> ::std::slice::Iter<'_, String> {
+        self.0.iter()
+    }
+
+    /// Whether the response didn't carry any warnings.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// A response type that can carry the [`Warnings`][Warnings] parsed from its head.
+///
+/// [Warnings]: struct.Warnings.html
+pub trait WithWarnings: Sized {
+    /// Attach warnings parsed from the response head.
+    ///
+    /// The default implementation ignores them, for response types that
+    /// don't have anywhere to put them.
+    fn with_warnings(self, _warnings: Warnings) -> Self {
+        self
+    }
+}
+
+impl WithWarnings for ::serde_json::Value {}
+
+/// Treat any `2xx` status as a success, and anything else as an `ApiError`.
+///
+/// This is the same status-based check used for `Value`, pulled out so the
+/// concrete response types can share it instead of re-implementing it.
+pub(crate) fn is_ok_from_status<B: ResponseBody>(head: HttpResponseHead, body: Unbuffered<B>) -> Result<MaybeOkResponse<B>, ParseResponseError> {
+    match head.status() {
+        200..=299 => Ok(MaybeOkResponse::ok(body)),
+        _ => Ok(MaybeOkResponse::err(body)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_head_collects_warning_headers_case_insensitively() {
+        let head = HttpResponseHead::from((200, vec![
+            ("warning".to_owned(), "299 Elasticsearch \"[a] is deprecated\"".to_owned()),
+            ("Content-Type".to_owned(), "application/json".to_owned()),
+            ("WARNING".to_owned(), "299 Elasticsearch \"[b] is deprecated\"".to_owned()),
+        ]));
+
+        let warnings = Warnings::from_head(&head);
+
+        let collected: Vec<_> = warnings.iter().collect();
+        assert_eq!(2, collected.len());
+        assert_eq!("299 Elasticsearch \"[a] is deprecated\"", collected[0]);
+        assert_eq!("299 Elasticsearch \"[b] is deprecated\"", collected[1]);
+        assert!(!warnings.is_empty());
+    }
+
+    #[test]
+    fn from_head_is_empty_without_warning_headers() {
+        let head = HttpResponseHead::from((200, vec![("Content-Type".to_owned(), "application/json".to_owned())]));
+
+        let warnings = Warnings::from_head(&head);
+
+        assert!(warnings.is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+//! Error types returned when parsing a response.
+
+use std::io;
+use serde::de::{Deserialize, Deserializer};
+use serde_json;
+
+use common::Warnings;
+
+quick_error! {
+    /// An error encountered while parsing a response body.
+    #[derive(Debug)]
+    pub enum ParseResponseError {
+        Io(err: io::Error) {
+            display("io error parsing response: {}", err)
+            cause(err)
+            from()
+        }
+        Json(err: serde_json::Error) {
+            display("json error parsing response: {}", err)
+            cause(err)
+            from()
+        }
+    }
+}
+
+quick_error! {
+    /// An error parsing a response, or an `ApiError` returned by Elasticsearch.
+    #[derive(Debug)]
+    pub enum ResponseError {
+        /// Elasticsearch returned a response that wasn't a success.
+        Api(err: ApiError, warnings: Warnings) {
+            display("elasticsearch returned an api error: {}", err)
+        }
+        /// The response body couldn't be parsed.
+        Parse(err: ParseResponseError) {
+            display("error parsing response: {}", err)
+            cause(err)
+            from()
+        }
+    }
+}
+
+/// A single node in the tree of causes Elasticsearch returns for a failed request.
+///
+/// Elasticsearch nests `root_cause` and `caused_by` around the main `type`/`reason`
+/// pair, so a single exception can explain both what ultimately went wrong and
+/// what triggered it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ElasticErrorCause {
+    /// The Elasticsearch exception type, like `index_not_found_exception`.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// A human-readable explanation of the error.
+    pub reason: Option<String>,
+    /// The index the error relates to, if there is one.
+    pub index: Option<String>,
+    /// The errors that caused this one, if Elasticsearch reported any.
+    #[serde(default)]
+    pub root_cause: Vec<ElasticErrorCause>,
+    /// The underlying error this one was caused by, if any.
+    pub caused_by: Option<Box<ElasticErrorCause>>,
+}
+
+/// A general API error received from Elasticsearch.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ApiError {
+    /// The targeted index doesn't exist.
+    IndexNotFound {
+        /// The index that wasn't found.
+        index: String,
+        /// The full error tree Elasticsearch returned.
+        cause: ElasticErrorCause,
+    },
+    /// An API error that doesn't map to one of the known variants above.
+    ///
+    /// The full error tree is still available, so callers can match on
+    /// `cause().ty` for error types this crate doesn't know about yet.
+    Other(ElasticErrorCause),
+}
+
+impl ApiError {
+    /// Get the full error tree Elasticsearch returned, regardless of which
+    /// variant (if any) it was classified into.
+    pub fn cause(&self) -> &ElasticErrorCause {
+        match *self {
+            ApiError::IndexNotFound { ref cause, .. } => cause,
+            ApiError::Other(ref cause) => cause,
+        }
+    }
+}
+
+impl ::std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        let cause = self.cause();
+
+        match cause.reason {
+            Some(ref reason) => write!(f, "{}: {}", cause.ty, reason),
+            None => write!(f, "{}", cause.ty),
+        }
+    }
+}
+
+impl From<ElasticErrorCause> for ApiError {
+    fn from(cause: ElasticErrorCause) -> Self {
+        match cause.ty.as_str() {
+            "index_not_found_exception" => {
+                let index = cause.index.clone().unwrap_or_default();
+
+                ApiError::IndexNotFound {
+                    index: index,
+                    cause: cause,
+                }
+            }
+            _ => ApiError::Other(cause),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ApiError {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        #[derive(Deserialize)]
+        struct ErrorResponse {
+            error: ElasticErrorCause,
+        }
+
+        let response = ErrorResponse::deserialize(deserializer)?;
+
+        Ok(ApiError::from(response.error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_variant_is_classified_from_type_and_index() {
+        let body = r#"{
+            "error": {
+                "root_cause": [
+                    { "type": "index_not_found_exception", "reason": "no such index [foo]", "index": "foo" }
+                ],
+                "type": "index_not_found_exception",
+                "reason": "no such index [foo]",
+                "index": "foo"
+            },
+            "status": 404
+        }"#;
+
+        let err: ApiError = ::serde_json::from_str(body).unwrap();
+
+        match err {
+            ApiError::IndexNotFound { ref index, .. } => assert_eq!("foo", index),
+            ref other => panic!("expected IndexNotFound, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unknown_variant_falls_back_to_other_but_keeps_full_cause_tree() {
+        let body = r#"{
+            "error": {
+                "root_cause": [
+                    { "type": "mapper_parsing_exception", "reason": "failed to parse field [a]" }
+                ],
+                "type": "mapper_parsing_exception",
+                "reason": "failed to parse field [a]",
+                "caused_by": {
+                    "type": "number_format_exception",
+                    "reason": "For input string: \"not-a-number\"",
+                    "caused_by": {
+                        "type": "illegal_argument_exception",
+                        "reason": "not-a-number"
+                    }
+                }
+            },
+            "status": 400
+        }"#;
+
+        let err: ApiError = ::serde_json::from_str(body).unwrap();
+
+        match err {
+            ApiError::Other(ref cause) => {
+                assert_eq!("mapper_parsing_exception", cause.ty);
+                assert_eq!(1, cause.root_cause.len());
+                assert_eq!("mapper_parsing_exception", cause.root_cause[0].ty);
+
+                let caused_by = cause.caused_by.as_ref().expect("missing caused_by");
+                assert_eq!("number_format_exception", caused_by.ty);
+
+                let caused_by = caused_by.caused_by.as_ref().expect("missing nested caused_by");
+                assert_eq!("illegal_argument_exception", caused_by.ty);
+                assert_eq!(Some("not-a-number".to_owned()), caused_by.reason);
+            }
+            ref other => panic!("expected Other, got {:?}", other),
+        }
+    }
+}
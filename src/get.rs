@@ -0,0 +1,68 @@
+//! The response from a get document request.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use common::{Warnings, WithWarnings};
+use error::ParseResponseError;
+use parsing::{HttpResponseHead, IsOk, MaybeOkResponse, ResponseBody, Unbuffered};
+
+/// The response from a get document request, for an arbitrary document type `T`.
+#[derive(Debug, Deserialize)]
+pub struct GetResponseOf<T = Value> {
+    #[serde(rename = "_index")]
+    pub index: String,
+    #[serde(rename = "_type")]
+    pub ty: String,
+    #[serde(rename = "_id")]
+    pub id: String,
+    /// Whether the document was found.
+    pub found: bool,
+    #[serde(rename = "_source", default)]
+    source: Option<T>,
+    #[serde(skip)]
+    warnings: Warnings,
+}
+
+/// The response from a get document request, for documents parsed as a `serde_json::Value`.
+pub type GetResponse = GetResponseOf<Value>;
+
+impl<T> GetResponseOf<T> {
+    /// Get the source document, if it was found and included in the response.
+    pub fn source(&self) -> Option<&T> {
+        self.source.as_ref()
+    }
+
+    /// Get any deprecation warnings returned alongside the response.
+    pub fn warnings(&self) -> &Warnings {
+        &self.warnings
+    }
+}
+
+impl<T: DeserializeOwned> IsOk for GetResponseOf<T> {
+    fn is_ok<B: ResponseBody>(head: HttpResponseHead, body: Unbuffered<B>) -> Result<MaybeOkResponse<B>, ParseResponseError> {
+        // A document that isn't found is still a successful response with
+        // `found: false`, not an `ApiError`. A 404 whose body carries a root
+        // `error` node (like a missing index) is an `ApiError`.
+        match head.status() {
+            200..=299 => Ok(MaybeOkResponse::ok(body)),
+            404 => {
+                let (maybe_err, body) = body.body()?;
+
+                let is_ok = maybe_err.as_object()
+                    .and_then(|maybe_err| maybe_err.get("error"))
+                    .is_none();
+
+                Ok(MaybeOkResponse::new(is_ok, body))
+            }
+            _ => Ok(MaybeOkResponse::err(body)),
+        }
+    }
+}
+
+impl<T> WithWarnings for GetResponseOf<T> {
+    fn with_warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = warnings;
+        self
+    }
+}
@@ -71,7 +71,7 @@
 //!     Ok(res) => {
 //!         // The document was not found
 //!     }
-//!     Err(ResponseError::Api(ApiError::IndexNotFound { index })) => {
+//!     Err(ResponseError::Api(ApiError::IndexNotFound { index, .. }, _warnings)) => {
 //!         // The index doesn't exist
 //!     }
 //!     _ => {
@@ -101,23 +101,25 @@ extern crate serde_json;
 extern crate slog_stdlog;
 extern crate slog_envlogger;
 
+#[cfg(feature = "async")]
+extern crate futures;
+
+#[cfg(feature = "async")]
+extern crate bytes;
+
 pub mod error;
 pub mod parsing;
 
 mod common;
 mod command;
-mod ping;
 mod get;
 mod search;
 mod bulk;
-mod index;
 
 pub use self::common::*;
 pub use self::command::*;
-pub use self::ping::*;
 pub use self::get::*;
 pub use self::search::*;
 pub use self::bulk::*;
-pub use self::index::*;
 
 pub use self::parsing::parse;
@@ -5,6 +5,12 @@ use std::io::{Cursor, Read};
 use serde::de::DeserializeOwned;
 use serde_json::{self, Value};
 
+#[cfg(feature = "async")]
+use bytes::{Bytes, BytesMut};
+#[cfg(feature = "async")]
+use futures::{Future, Stream};
+
+use common::{Warnings, WithWarnings};
 use error::*;
 
 /// A parser that separates taking a response type from the readable body type.
@@ -19,7 +25,7 @@ pub fn parse<T: IsOk + DeserializeOwned>() -> Parse<T> {
     }
 }
 
-impl<T: IsOk + DeserializeOwned> Parse<T> {
+impl<T: IsOk + DeserializeOwned + WithWarnings> Parse<T> {
     /// Try parse a contiguous slice of bytes into a concrete response.
     pub fn from_slice<B: AsRef<[u8]>, H: Into<HttpResponseHead>>(self, head: H, body: B) -> Result<T, ResponseError> {
         from_body(head.into(), SliceBody(body))
@@ -29,19 +35,45 @@ impl<T: IsOk + DeserializeOwned> Parse<T> {
     pub fn from_reader<B: Read, H: Into<HttpResponseHead>>(self, head: H, body: B) -> Result<T, ResponseError> {
         from_body(head.into(), ReadBody(body))
     }
+
+    /// Try parse a stream of byte chunks into a concrete response.
+    ///
+    /// The stream is concatenated into a single buffer before being inspected,
+    /// so this doesn't avoid buffering the whole body, but it does let callers
+    /// hand over a body before it's finished arriving over the wire.
+    #[cfg(feature = "async")]
+    pub fn from_stream<S, H>(self, head: H, body: S) -> Box<Future<Item = T, Error = ResponseError>>
+        where S: Stream<Item = Bytes> + 'static,
+              S::Error: Into<ResponseError>,
+              H: Into<HttpResponseHead>,
+              T: 'static
+    {
+        let head = head.into();
+
+        let fut = body.map_err(Into::into)
+            .fold(BytesMut::new(), |mut buf, chunk| {
+                buf.extend_from_slice(&chunk);
+                Ok(buf) as Result<_, ResponseError>
+            })
+            .and_then(move |buf| from_body(head, SliceBody(buf.freeze())));
+
+        Box::new(fut)
+    }
 }
 
-fn from_body<B: ResponseBody, T: IsOk + DeserializeOwned>(head: HttpResponseHead, body: B) -> Result<T, ResponseError> {
+fn from_body<B: ResponseBody, T: IsOk + DeserializeOwned + WithWarnings>(head: HttpResponseHead, body: B) -> Result<T, ResponseError> {
+    let warnings = Warnings::from_head(&head);
+
     let maybe = T::is_ok(head, Unbuffered(body))?;
 
     match maybe.ok {
         true => {
-            let ok = maybe.res.parse_ok()?;
-            Ok(ok)
+            let ok: T = maybe.res.parse_ok()?;
+            Ok(ok.with_warnings(warnings))
         }
         false => {
             let err = maybe.res.parse_err()?;
-            Err(ResponseError::Api(err))
+            Err(ResponseError::Api(err, warnings))
         }
     }
 }
@@ -49,6 +81,7 @@ fn from_body<B: ResponseBody, T: IsOk + DeserializeOwned>(head: HttpResponseHead
 /// The non-body component of the HTTP response.
 pub struct HttpResponseHead {
     code: u16,
+    headers: Vec<(String, String)>,
 }
 
 impl HttpResponseHead {
@@ -56,16 +89,146 @@ impl HttpResponseHead {
     pub fn status(&self) -> u16 {
         self.code
     }
+
+    /// Get the value of a header by name.
+    ///
+    /// Header names are matched case-insensitively, as per the HTTP spec.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|&(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Get all headers on the response.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
 }
 
 impl From<u16> for HttpResponseHead {
     fn from(status: u16) -> Self {
         HttpResponseHead {
-            code: status
+            code: status,
+            headers: Vec::new(),
+        }
+    }
+}
+
+impl From<(u16, Vec<(String, String)>)> for HttpResponseHead {
+    fn from(head: (u16, Vec<(String, String)>)) -> Self {
+        HttpResponseHead {
+            code: head.0,
+            headers: head.1,
         }
     }
 }
 
+/// Incrementally builds a response head and body, then feeds both into the
+/// `parse` pipeline.
+///
+/// This is the single chokepoint equivalent to what `elastic`'s
+/// `SyncResponseBuilder`/`AsyncResponseBuilder` do around this crate: a
+/// custom `IsOk` type can go straight from a status, some headers and a body
+/// source to a concrete response without hand-assembling an `HttpResponseHead`.
+///
+/// ```no_run
+/// # extern crate elastic_responses;
+/// # use elastic_responses::*;
+/// # use elastic_responses::parsing::ResponseBuilder;
+/// # fn do_request() -> Vec<u8> { unimplemented!() }
+/// # fn main() {
+/// let response = ResponseBuilder::new(200)
+///     .header("X-Elastic-Product", "Elasticsearch")
+///     .body(do_request())
+///     .into_response::<SearchResponse>()
+///     .unwrap();
+/// # }
+/// ```
+pub struct ResponseBuilder<B> {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: B,
+}
+
+impl ResponseBuilder<()> {
+    /// Start building a response with the given status code and no body yet.
+    pub fn new(status: u16) -> Self {
+        ResponseBuilder {
+            status: status,
+            headers: Vec::new(),
+            body: (),
+        }
+    }
+}
+
+impl<B> ResponseBuilder<B> {
+    /// Add a header to the response.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Use a contiguous slice of bytes as the response body.
+    pub fn body<NB: AsRef<[u8]>>(self, body: NB) -> ResponseBuilder<SliceBody<NB>> {
+        ResponseBuilder {
+            status: self.status,
+            headers: self.headers,
+            body: SliceBody(body),
+        }
+    }
+
+    /// Use an arbitrary reader as the response body.
+    pub fn reader<R: Read>(self, body: R) -> ResponseBuilder<ReadBody<R>> {
+        ResponseBuilder {
+            status: self.status,
+            headers: self.headers,
+            body: ReadBody(body),
+        }
+    }
+}
+
+impl<B: ResponseBody> ResponseBuilder<B> {
+    /// Parse the response into a concrete type.
+    pub fn into_response<T: IsOk + DeserializeOwned + WithWarnings>(self) -> Result<T, ResponseError> {
+        from_body(HttpResponseHead::from((self.status, self.headers)), self.body)
+    }
+}
+
+#[cfg(feature = "async")]
+impl ResponseBuilder<()> {
+    /// Use a stream of byte chunks as the response body.
+    pub fn stream<S>(self, body: S) -> StreamResponseBuilder<S> {
+        StreamResponseBuilder {
+            head: (self.status, self.headers),
+            body: body,
+        }
+    }
+}
+
+/// A [`ResponseBuilder`][ResponseBuilder] whose body is an asynchronous stream.
+///
+/// Kept separate from [`ResponseBuilder`][ResponseBuilder] because parsing it
+/// returns a `Future` rather than a `Result`.
+///
+/// [ResponseBuilder]: struct.ResponseBuilder.html
+#[cfg(feature = "async")]
+pub struct StreamResponseBuilder<S> {
+    head: (u16, Vec<(String, String)>),
+    body: S,
+}
+
+#[cfg(feature = "async")]
+impl<S> StreamResponseBuilder<S>
+    where S: Stream<Item = Bytes> + 'static,
+          S::Error: Into<ResponseError>
+{
+    /// Parse the response into a concrete type.
+    pub fn into_response<T: IsOk + DeserializeOwned + WithWarnings + 'static>(self) -> Box<Future<Item = T, Error = ResponseError>> {
+        parse::<T>().from_stream(HttpResponseHead::from(self.head), self.body)
+    }
+}
+
 /// A http response body that can be buffered into a json value.
 pub trait ResponseBody where Self: Sized
 {
@@ -82,7 +245,8 @@ pub trait ResponseBody where Self: Sized
     fn parse_err(self) -> Result<ApiError, ParseResponseError>;
 }
 
-struct ReadBody<B>(B);
+/// A response body backed by an arbitrary reader.
+pub struct ReadBody<B>(B);
 
 impl<B: Read> ResponseBody for ReadBody<B> {
     type Buffered = SliceBody<Vec<u8>>;
@@ -105,7 +269,8 @@ impl<B: Read> ResponseBody for ReadBody<B> {
     }
 }
 
-struct SliceBody<B>(B);
+/// A response body backed by a contiguous slice of bytes.
+pub struct SliceBody<B>(B);
 
 impl<B: AsRef<[u8]>> ResponseBody for SliceBody<B> {
     type Buffered = Self;
@@ -234,4 +399,35 @@ impl<B> From<Buffered<B>> for MaybeBufferedResponse<B>
     fn from(value: Buffered<B>) -> Self {
         MaybeBufferedResponse::Buffered(value.0)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_matches_name_case_insensitively() {
+        let head = HttpResponseHead::from((200, vec![("X-Elastic-Product".to_owned(), "Elasticsearch".to_owned())]));
+
+        assert_eq!(Some("Elasticsearch"), head.header("x-elastic-product"));
+        assert_eq!(Some("Elasticsearch"), head.header("X-ELASTIC-PRODUCT"));
+    }
+
+    #[test]
+    fn header_is_none_when_missing() {
+        let head = HttpResponseHead::from((200, vec![("Content-Type".to_owned(), "application/json".to_owned())]));
+
+        assert_eq!(None, head.header("x-elastic-product"));
+    }
+
+    #[test]
+    fn headers_returns_every_header_in_order() {
+        let raw = vec![
+            ("Content-Type".to_owned(), "application/json".to_owned()),
+            ("Warning".to_owned(), "299 Elasticsearch \"[types removal] ...\"".to_owned()),
+        ];
+        let head = HttpResponseHead::from((200, raw.clone()));
+
+        assert_eq!(raw.as_slice(), head.headers());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,73 @@
+//! The response from a search request.
+
+use std::collections::BTreeMap;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use common::{self, Warnings, WithWarnings};
+use error::ParseResponseError;
+use parsing::{HttpResponseHead, IsOk, MaybeOkResponse, ResponseBody, Unbuffered};
+
+/// A search response that can be iterated over for hits and aggregations.
+#[derive(Debug, Default, Deserialize)]
+pub struct SearchResponse<T = Value> {
+    took: u64,
+    #[serde(default)]
+    timed_out: bool,
+    hits: Hits<T>,
+    #[serde(default)]
+    aggregations: Option<BTreeMap<String, Value>>,
+    #[serde(skip)]
+    warnings: Warnings,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Hits<T> {
+    hits: Vec<Hit<T>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Hit<T> {
+    #[serde(rename = "_source")]
+    source: T,
+}
+
+impl<T> SearchResponse<T> {
+    /// The time taken to execute the search, in milliseconds.
+    pub fn took(&self) -> u64 {
+        self.took
+    }
+
+    /// Whether the search timed out.
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    /// Iterate over the documents returned by the search.
+    pub fn hits(&self) -> impl Iterator<Item = &T> {
+        self.hits.hits.iter().map(|hit| &hit.source)
+    }
+
+    /// Iterate over the named aggregation results, if there are any.
+    pub fn aggs(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.aggregations.iter().flat_map(|aggs| aggs.iter())
+    }
+
+    /// Get any deprecation warnings returned alongside the response.
+    pub fn warnings(&self) -> &Warnings {
+        &self.warnings
+    }
+}
+
+impl<T: DeserializeOwned> IsOk for SearchResponse<T> {
+    fn is_ok<B: ResponseBody>(head: HttpResponseHead, body: Unbuffered<B>) -> Result<MaybeOkResponse<B>, ParseResponseError> {
+        common::is_ok_from_status(head, body)
+    }
+}
+
+impl<T> WithWarnings for SearchResponse<T> {
+    fn with_warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = warnings;
+        self
+    }
+}